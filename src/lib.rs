@@ -1,3 +1,13 @@
+pub mod modulation;
+#[cfg(feature = "noise")]
+pub mod noise;
+pub mod numerics;
+pub mod prelude;
+pub mod random;
+#[cfg(feature = "sim")]
+pub mod sim;
+pub mod tiling;
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,275 @@
+//! Time-driven parameter sources for animating generation and style parameters.
+//!
+//! A [`Modulator`] maps a time (in seconds) to a scalar value. The crate knows nothing about
+//! audio or other I/O; signals from the host (e.g. FFT band energies) are pushed into a
+//! [`SampleBuffer`], which can then be used anywhere a modulator is accepted.
+
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+
+/// A source of scalar values varying over time.
+pub trait Modulator {
+    /// Returns the value of this modulator at `time` seconds.
+    fn value_at(&self, time: f64) -> f64;
+}
+
+impl<F: Fn(f64) -> f64> Modulator for F {
+    fn value_at(&self, time: f64) -> f64 {
+        self(time)
+    }
+}
+
+/// The shape of a single cycle of an [`Lfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+}
+
+impl Waveform {
+    /// Evaluates the waveform at `phase` cycles, returning a value in `[-1, 1]`.
+    pub fn evaluate(self, phase: f64) -> f64 {
+        let phase = phase.rem_euclid(1.0);
+        match self {
+            Waveform::Sine => (phase * TAU).sin(),
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            Waveform::Saw => 2.0 * phase - 1.0,
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+}
+
+/// A low-frequency oscillator producing `offset + amplitude * waveform(frequency * t + phase)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lfo {
+    pub waveform: Waveform,
+    /// Cycles per second.
+    pub frequency: f64,
+    /// Phase offset in cycles.
+    pub phase: f64,
+    pub amplitude: f64,
+    pub offset: f64,
+}
+
+impl Lfo {
+    /// Creates a unit-amplitude oscillator centered on zero with no phase offset.
+    pub fn new(waveform: Waveform, frequency: f64) -> Self {
+        Self {
+            waveform,
+            frequency,
+            phase: 0.0,
+            amplitude: 1.0,
+            offset: 0.0,
+        }
+    }
+
+    /// Returns a copy of this oscillator with the given phase offset (in cycles).
+    pub fn with_phase(self, phase: f64) -> Self {
+        Self { phase, ..self }
+    }
+
+    /// Returns a copy of this oscillator oscillating over `[min, max]`.
+    pub fn with_range(self, min: f64, max: f64) -> Self {
+        Self {
+            amplitude: (max - min) / 2.0,
+            offset: (max + min) / 2.0,
+            ..self
+        }
+    }
+}
+
+impl Modulator for Lfo {
+    fn value_at(&self, time: f64) -> f64 {
+        self.offset + self.amplitude * self.waveform.evaluate(self.frequency * time + self.phase)
+    }
+}
+
+/// An attack-decay-sustain-release envelope with a gate opened at `gate_on` and optionally
+/// closed at `gate_off`. Values range from `0` to `1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    pub attack: f64,
+    pub decay: f64,
+    pub sustain: f64,
+    pub release: f64,
+    pub gate_on: f64,
+    pub gate_off: Option<f64>,
+}
+
+impl Envelope {
+    /// Creates an envelope whose gate opens at `gate_on` and stays open.
+    pub fn new(attack: f64, decay: f64, sustain: f64, release: f64, gate_on: f64) -> Self {
+        Self {
+            attack,
+            decay,
+            sustain,
+            release,
+            gate_on,
+            gate_off: None,
+        }
+    }
+
+    /// Closes the gate at `time`, starting the release stage.
+    pub fn release_at(&mut self, time: f64) {
+        self.gate_off = Some(time);
+    }
+
+    fn gated_level(&self, elapsed: f64) -> f64 {
+        if elapsed < 0.0 {
+            0.0
+        } else if elapsed < self.attack {
+            elapsed / self.attack
+        } else if elapsed < self.attack + self.decay {
+            let t = (elapsed - self.attack) / self.decay;
+            1.0 + (self.sustain - 1.0) * t
+        } else {
+            self.sustain
+        }
+    }
+}
+
+impl Modulator for Envelope {
+    fn value_at(&self, time: f64) -> f64 {
+        match self.gate_off {
+            Some(off) if time >= off => {
+                let level = self.gated_level(off - self.gate_on);
+                let released = time - off;
+                if released >= self.release {
+                    0.0
+                } else {
+                    level * (1.0 - released / self.release)
+                }
+            }
+            _ => self.gated_level(time - self.gate_on),
+        }
+    }
+}
+
+/// A bounded buffer of host-supplied samples taken at a fixed rate, starting at `start_time`.
+///
+/// Values between samples are linearly interpolated; times outside the buffered range are
+/// clamped to the oldest or newest sample. An empty buffer evaluates to `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleBuffer {
+    sample_rate: f64,
+    start_time: f64,
+    capacity: usize,
+    samples: VecDeque<f64>,
+}
+
+impl SampleBuffer {
+    /// Creates an empty buffer holding at most `capacity` samples taken `sample_rate` times
+    /// per second, the first of which corresponds to `start_time`.
+    pub fn new(sample_rate: f64, start_time: f64, capacity: usize) -> Self {
+        assert!(sample_rate > 0.0, "Sample rate must be positive.");
+        assert!(capacity > 0, "Capacity must be non-zero.");
+        Self {
+            sample_rate,
+            start_time,
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Appends a sample, discarding the oldest one if the buffer is full.
+    pub fn push(&mut self, sample: f64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+            self.start_time += 1.0 / self.sample_rate;
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// The time corresponding to the oldest buffered sample.
+    pub fn start_time(&self) -> f64 {
+        self.start_time
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+impl Modulator for SampleBuffer {
+    fn value_at(&self, time: f64) -> f64 {
+        let last = match self.samples.len() {
+            0 => return 0.0,
+            n => n - 1,
+        };
+        let position = ((time - self.start_time) * self.sample_rate).clamp(0.0, last as f64);
+        let index = position.floor() as usize;
+        if index == last {
+            return self.samples[last];
+        }
+        let t = position - index as f64;
+        self.samples[index] * (1.0 - t) + self.samples[index + 1] * t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn lfo_waveforms() {
+        let sine = Lfo::new(Waveform::Sine, 2.0);
+        assert_close(sine.value_at(0.125), 1.0);
+        let triangle = Lfo::new(Waveform::Triangle, 1.0).with_range(0.0, 10.0);
+        assert_close(triangle.value_at(0.0), 0.0);
+        assert_close(triangle.value_at(0.5), 10.0);
+        assert_close(triangle.value_at(0.25), 5.0);
+        let square = Lfo::new(Waveform::Square, 1.0).with_phase(0.5);
+        assert_close(square.value_at(0.0), -1.0);
+    }
+
+    #[test]
+    fn envelope_stages() {
+        let mut envelope = Envelope::new(1.0, 1.0, 0.5, 2.0, 10.0);
+        assert_close(envelope.value_at(9.0), 0.0);
+        assert_close(envelope.value_at(10.5), 0.5);
+        assert_close(envelope.value_at(11.0), 1.0);
+        assert_close(envelope.value_at(11.5), 0.75);
+        assert_close(envelope.value_at(100.0), 0.5);
+        envelope.release_at(20.0);
+        assert_close(envelope.value_at(21.0), 0.25);
+        assert_close(envelope.value_at(23.0), 0.0);
+    }
+
+    #[test]
+    fn sample_buffer_interpolates_and_evicts() {
+        let mut buffer = SampleBuffer::new(10.0, 0.0, 3);
+        assert_close(buffer.value_at(0.0), 0.0);
+        buffer.push(1.0);
+        buffer.push(3.0);
+        assert_close(buffer.value_at(0.05), 2.0);
+        assert_close(buffer.value_at(5.0), 3.0);
+        buffer.push(5.0);
+        buffer.push(7.0);
+        assert_eq!(buffer.len(), 3);
+        assert_close(buffer.start_time(), 0.1);
+        assert_close(buffer.value_at(0.0), 3.0);
+        assert_close(buffer.value_at(0.25), 6.0);
+    }
+
+    #[test]
+    fn closures_are_modulators() {
+        let ramp = |t: f64| 2.0 * t;
+        assert_close(ramp.value_at(3.0), 6.0);
+    }
+}
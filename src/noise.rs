@@ -0,0 +1,527 @@
+//! Seeded coherent noise: Perlin and OpenSimplex2 gradient noise in one to three dimensions,
+//! with tileable variants, plus fractal (fBm) octave summation.
+//!
+//! All generators are deterministic for a given seed and return values in approximately
+//! `[-1, 1]`. Coordinates are in lattice units, so features are roughly one unit across; scale
+//! the input to change the frequency.
+
+use crate::random::Rng;
+
+/// One-dimensional noise.
+pub trait Noise1 {
+    fn sample1(&self, x: f64) -> f64;
+}
+
+/// Two-dimensional noise.
+pub trait Noise2 {
+    fn sample2(&self, x: f64, y: f64) -> f64;
+}
+
+/// Three-dimensional noise.
+pub trait Noise3 {
+    fn sample3(&self, x: f64, y: f64, z: f64) -> f64;
+}
+
+const GRADIENTS2: [(f64, f64); 8] = [
+    (1.0, 1.0),
+    (-1.0, 1.0),
+    (1.0, -1.0),
+    (-1.0, -1.0),
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+];
+
+const GRADIENTS3: [(f64, f64, f64); 12] = [
+    (1.0, 1.0, 0.0),
+    (-1.0, 1.0, 0.0),
+    (1.0, -1.0, 0.0),
+    (-1.0, -1.0, 0.0),
+    (1.0, 0.0, 1.0),
+    (-1.0, 0.0, 1.0),
+    (1.0, 0.0, -1.0),
+    (-1.0, 0.0, -1.0),
+    (0.0, 1.0, 1.0),
+    (0.0, -1.0, 1.0),
+    (0.0, 1.0, -1.0),
+    (0.0, -1.0, -1.0),
+];
+
+/// A seeded permutation of `0..256` used to hash lattice points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Permutation([u8; 256]);
+
+impl Permutation {
+    fn new(seed: u64) -> Self {
+        let mut table = [0u8; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = i as u8;
+        }
+        Rng::new(seed).shuffle(&mut table);
+        Self(table)
+    }
+
+    fn hash1(&self, i: i64) -> usize {
+        self.0[(i & 255) as usize] as usize
+    }
+
+    fn hash2(&self, i: i64, j: i64) -> usize {
+        self.0[(self.hash1(i) + (j & 255) as usize) & 255] as usize
+    }
+
+    fn hash3(&self, i: i64, j: i64, k: i64) -> usize {
+        self.0[(self.hash2(i, j) + (k & 255) as usize) & 255] as usize
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Improved Perlin gradient noise.
+///
+/// Noise is zero at every integer lattice point. A tileable generator (see
+/// [`Perlin::tileable`]) repeats with the given integer period along every axis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Perlin {
+    permutation: Permutation,
+    period: Option<i64>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            permutation: Permutation::new(seed),
+            period: None,
+        }
+    }
+
+    /// Creates a generator that repeats every `period` units along each axis.
+    pub fn tileable(seed: u64, period: u32) -> Self {
+        assert!(period > 0, "Period must be non-zero.");
+        Self {
+            permutation: Permutation::new(seed),
+            period: Some(period as i64),
+        }
+    }
+
+    fn wrap(&self, i: i64) -> i64 {
+        match self.period {
+            Some(period) => i.rem_euclid(period),
+            None => i,
+        }
+    }
+
+    fn gradient1(&self, i: i64, x: f64) -> f64 {
+        let hash = self.permutation.hash1(self.wrap(i));
+        let gradient = 1.0 + (hash & 7) as f64 / 7.0;
+        if hash & 8 == 0 {
+            gradient * x
+        } else {
+            -gradient * x
+        }
+    }
+
+    fn gradient2(&self, i: i64, j: i64, x: f64, y: f64) -> f64 {
+        let hash = self.permutation.hash2(self.wrap(i), self.wrap(j));
+        let (gx, gy) = GRADIENTS2[hash & 7];
+        gx * x + gy * y
+    }
+
+    fn gradient3(&self, i: i64, j: i64, k: i64, x: f64, y: f64, z: f64) -> f64 {
+        let hash = self
+            .permutation
+            .hash3(self.wrap(i), self.wrap(j), self.wrap(k));
+        let (gx, gy, gz) = GRADIENTS3[hash % 12];
+        gx * x + gy * y + gz * z
+    }
+}
+
+impl Noise1 for Perlin {
+    fn sample1(&self, x: f64) -> f64 {
+        let i = x.floor();
+        let fx = x - i;
+        let i = i as i64;
+        let n0 = self.gradient1(i, fx);
+        let n1 = self.gradient1(i + 1, fx - 1.0);
+        lerp(n0, n1, fade(fx))
+    }
+}
+
+impl Noise2 for Perlin {
+    fn sample2(&self, x: f64, y: f64) -> f64 {
+        let (i, j) = (x.floor(), y.floor());
+        let (fx, fy) = (x - i, y - j);
+        let (i, j) = (i as i64, j as i64);
+        let (u, v) = (fade(fx), fade(fy));
+        let n00 = self.gradient2(i, j, fx, fy);
+        let n10 = self.gradient2(i + 1, j, fx - 1.0, fy);
+        let n01 = self.gradient2(i, j + 1, fx, fy - 1.0);
+        let n11 = self.gradient2(i + 1, j + 1, fx - 1.0, fy - 1.0);
+        lerp(lerp(n00, n10, u), lerp(n01, n11, u), v)
+    }
+}
+
+impl Noise3 for Perlin {
+    fn sample3(&self, x: f64, y: f64, z: f64) -> f64 {
+        let (i, j, k) = (x.floor(), y.floor(), z.floor());
+        let (fx, fy, fz) = (x - i, y - j, z - k);
+        let (i, j, k) = (i as i64, j as i64, k as i64);
+        let (u, v, w) = (fade(fx), fade(fy), fade(fz));
+        let corner = |di: i64, dj: i64, dk: i64| {
+            self.gradient3(
+                i + di,
+                j + dj,
+                k + dk,
+                fx - di as f64,
+                fy - dj as f64,
+                fz - dk as f64,
+            )
+        };
+        let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u);
+        let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u);
+        let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u);
+        let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u);
+        lerp(lerp(x00, x10, v), lerp(x01, x11, v), w)
+    }
+}
+
+/// Unit gradients at 15° intervals, offset by 7.5° from the axes, as `cos` of the first six
+/// angles; the rest follow by symmetry.
+const OPEN_SIMPLEX_COSINES: [f64; 6] = [
+    0.991_444_861_373_810_4,
+    0.923_879_532_511_286_7,
+    0.793_353_340_291_235_2,
+    0.608_761_429_008_720_7,
+    0.382_683_432_365_089_84,
+    0.130_526_192_220_051_7,
+];
+
+/// One of 24 evenly spaced unit gradients.
+fn open_simplex_gradient2(hash: usize) -> (f64, f64) {
+    let index = hash % 24;
+    let (c, s) = (
+        OPEN_SIMPLEX_COSINES[index % 6],
+        OPEN_SIMPLEX_COSINES[5 - index % 6],
+    );
+    match index / 6 {
+        0 => (c, s),
+        1 => (-s, c),
+        2 => (-c, -s),
+        _ => (s, -c),
+    }
+}
+
+/// One of 48 gradients of equal length: per pair of axes and quadrant, two that lean out of the
+/// plane and two within it.
+fn open_simplex_gradient3(hash: usize) -> (f64, f64, f64) {
+    const DIAGONAL: f64 = 2.224_744_871_391_589; // 1 + sqrt(3 / 2)
+    const LONG: f64 = 3.086_266_468_797_201_7;
+    const SHORT: f64 = 1.172_151_342_246_497_8;
+    let index = hash % 48;
+    let (sa, sb) = (
+        if index & 1 == 0 { 1.0 } else { -1.0 },
+        if index & 2 == 0 { 1.0 } else { -1.0 },
+    );
+    let (a, b, c) = match (index >> 2) % 4 {
+        0 => (DIAGONAL, DIAGONAL, 1.0),
+        1 => (DIAGONAL, DIAGONAL, -1.0),
+        2 => (LONG, SHORT, 0.0),
+        _ => (SHORT, LONG, 0.0),
+    };
+    let (a, b) = (sa * a, sb * b);
+    match index / 16 {
+        0 => (a, b, c),
+        1 => (c, a, b),
+        _ => (b, c, a),
+    }
+}
+
+/// OpenSimplex2 gradient noise (the fast variant): a triangular lattice in two dimensions and a
+/// body-centered cubic lattice in three, with radially attenuated contributions from the nearest
+/// lattice points.
+///
+/// Compared to [`Perlin`], it has fewer directional artifacts. A tileable generator (see
+/// [`OpenSimplex2::tileable`]) evaluates the cubic lattice in its axis-aligned orientation, so it
+/// repeats with the given integer period along every axis at the cost of some isotropy; its 1D
+/// and 2D samples are slices of the 3D noise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenSimplex2 {
+    permutation: Permutation,
+    period: Option<i64>,
+}
+
+impl OpenSimplex2 {
+    const SKEW2: f64 = 0.366_025_403_784_438_6; // (sqrt(3) - 1) / 2
+    const UNSKEW2: f64 = 0.211_324_865_405_187_1; // (3 - sqrt(3)) / 6
+    const RADIUS2: f64 = 0.5;
+    const SCALE2: f64 = 99.836_854_463_036_47;
+    const ROTATE3: f64 = 2.0 / 3.0;
+    const RADIUS3: f64 = 0.5;
+    const SCALE3: f64 = 32.802_013_769_865_77;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            permutation: Permutation::new(seed),
+            period: None,
+        }
+    }
+
+    /// Creates a generator that repeats every `period` units along each axis.
+    pub fn tileable(seed: u64, period: u32) -> Self {
+        assert!(period > 0, "Period must be non-zero.");
+        Self {
+            permutation: Permutation::new(seed),
+            period: Some(period as i64),
+        }
+    }
+
+    fn wrap(&self, i: i64) -> i64 {
+        match self.period {
+            Some(period) => i.rem_euclid(period),
+            None => i,
+        }
+    }
+
+    fn contribution2(&self, i: i64, j: i64, x: f64, y: f64) -> f64 {
+        let attenuation = Self::RADIUS2 - x * x - y * y;
+        if attenuation <= 0.0 {
+            return 0.0;
+        }
+        let (gx, gy) = open_simplex_gradient2(self.permutation.hash2(i, j));
+        let squared = attenuation * attenuation;
+        squared * squared * (gx * x + gy * y)
+    }
+
+    /// The contribution of point `(i, j, k)` of one of the two cubic lattices, `copy`, making up
+    /// the body-centered cubic lattice.
+    fn contribution3(&self, copy: i64, (i, j, k): (i64, i64, i64), x: f64, y: f64, z: f64) -> f64 {
+        let attenuation = Self::RADIUS3 - x * x - y * y - z * z;
+        if attenuation <= 0.0 {
+            return 0.0;
+        }
+        let hash = self
+            .permutation
+            .hash3(self.wrap(i), self.wrap(j), self.wrap(k));
+        let (gx, gy, gz) = open_simplex_gradient3(self.permutation.hash2(hash as i64, copy));
+        let squared = attenuation * attenuation;
+        squared * squared * (gx * x + gy * y + gz * z)
+    }
+
+    /// Noise on the body-centered cubic lattice, in the lattice's own axis-aligned frame.
+    fn lattice3(&self, x: f64, y: f64, z: f64) -> f64 {
+        let mut value = 0.0;
+        // The second cubic lattice is offset by half a cell; shifting the point instead lets both
+        // use integer indices.
+        for copy in 0..2 {
+            let offset = 0.5 * copy as f64;
+            let (x, y, z) = (x + offset, y + offset, z + offset);
+            let (i, j, k) = (x.round(), y.round(), z.round());
+            let (dx, dy, dz) = (x - i, y - j, z - k);
+            let cell = (i as i64, j as i64, k as i64);
+            value += self.contribution3(copy, cell, dx, dy, dz);
+
+            // Only the neighbor across the face nearest the point can also be in range.
+            let step = |d: f64| if d > 0.0 { 1 } else { -1 };
+            let (ax, ay, az) = (dx.abs(), dy.abs(), dz.abs());
+            let (neighbor, (dx, dy, dz)) = if ax >= ay && ax >= az {
+                let s = step(dx);
+                ((cell.0 + s, cell.1, cell.2), (dx - s as f64, dy, dz))
+            } else if ay >= az {
+                let s = step(dy);
+                ((cell.0, cell.1 + s, cell.2), (dx, dy - s as f64, dz))
+            } else {
+                let s = step(dz);
+                ((cell.0, cell.1, cell.2 + s), (dx, dy, dz - s as f64))
+            };
+            value += self.contribution3(copy, neighbor, dx, dy, dz);
+        }
+        Self::SCALE3 * value
+    }
+}
+
+impl Noise1 for OpenSimplex2 {
+    fn sample1(&self, x: f64) -> f64 {
+        self.sample2(x, 0.0)
+    }
+}
+
+impl Noise2 for OpenSimplex2 {
+    fn sample2(&self, x: f64, y: f64) -> f64 {
+        if self.period.is_some() {
+            return self.lattice3(x, y, 0.0);
+        }
+        let skew = (x + y) * Self::SKEW2;
+        let (i, j) = ((x + skew).floor(), (y + skew).floor());
+        let unskew = (i + j) * Self::UNSKEW2;
+        let (x0, y0) = (x - (i - unskew), y - (j - unskew));
+        let (i, j) = (i as i64, j as i64);
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let (x1, y1) = (
+            x0 - i1 as f64 + Self::UNSKEW2,
+            y0 - j1 as f64 + Self::UNSKEW2,
+        );
+        let (x2, y2) = (
+            x0 - 1.0 + 2.0 * Self::UNSKEW2,
+            y0 - 1.0 + 2.0 * Self::UNSKEW2,
+        );
+        Self::SCALE2
+            * (self.contribution2(i, j, x0, y0)
+                + self.contribution2(i + i1, j + j1, x1, y1)
+                + self.contribution2(i + 1, j + 1, x2, y2))
+    }
+}
+
+impl Noise3 for OpenSimplex2 {
+    fn sample3(&self, x: f64, y: f64, z: f64) -> f64 {
+        if self.period.is_some() {
+            return self.lattice3(x, y, z);
+        }
+        // Reflect the lattice so its main diagonal lies along the vertical axis, which hides the
+        // cubic structure in horizontal slices.
+        let r = Self::ROTATE3 * (x + y + z);
+        self.lattice3(r - x, r - y, r - z)
+    }
+}
+
+/// Fractal Brownian motion: a sum of `octaves` copies of `source`, each sampled at `lacunarity`
+/// times the frequency and `gain` times the amplitude of the previous one.
+///
+/// The sum is normalized by the total amplitude so the output range matches the source. Summing
+/// a tileable source with an integer lacunarity remains tileable with the same period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fbm<N> {
+    pub source: N,
+    pub octaves: u32,
+    pub lacunarity: f64,
+    pub gain: f64,
+}
+
+impl<N> Fbm<N> {
+    /// Creates an fBm combinator with the conventional lacunarity of `2` and gain of `0.5`.
+    pub fn new(source: N, octaves: u32) -> Self {
+        assert!(octaves > 0, "At least one octave is required.");
+        Self {
+            source,
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    fn accumulate(&self, mut sample: impl FnMut(f64) -> f64) -> f64 {
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        let (mut total, mut norm) = (0.0, 0.0);
+        for _ in 0..self.octaves {
+            total += amplitude * sample(frequency);
+            norm += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        total / norm
+    }
+}
+
+impl<N: Noise1> Noise1 for Fbm<N> {
+    fn sample1(&self, x: f64) -> f64 {
+        self.accumulate(|f| self.source.sample1(x * f))
+    }
+}
+
+impl<N: Noise2> Noise2 for Fbm<N> {
+    fn sample2(&self, x: f64, y: f64) -> f64 {
+        self.accumulate(|f| self.source.sample2(x * f, y * f))
+    }
+}
+
+impl<N: Noise3> Noise3 for Fbm<N> {
+    fn sample3(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.accumulate(|f| self.source.sample3(x * f, y * f, z * f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> impl Iterator<Item = (f64, f64, f64)> {
+        (0..20).flat_map(|i| {
+            (0..20).flat_map(move |j| {
+                (0..5).map(move |k| (i as f64 * 0.37, j as f64 * 0.29 - 3.0, k as f64 * 0.53))
+            })
+        })
+    }
+
+    #[test]
+    fn same_seed_is_deterministic() {
+        let (a, b) = (Perlin::new(7), Perlin::new(7));
+        let (c, d) = (OpenSimplex2::new(7), OpenSimplex2::new(7));
+        for (x, y, z) in grid() {
+            assert_eq!(a.sample3(x, y, z), b.sample3(x, y, z));
+            assert_eq!(c.sample2(x, y), d.sample2(x, y));
+        }
+        assert_ne!(
+            Perlin::new(1).sample2(0.5, 0.5),
+            Perlin::new(2).sample2(0.5, 0.5)
+        );
+    }
+
+    #[test]
+    fn values_are_bounded() {
+        let perlin = Perlin::new(3);
+        let simplex = OpenSimplex2::new(3);
+        let tiled = OpenSimplex2::tileable(3, 4);
+        let fbm = Fbm::new(OpenSimplex2::new(3), 4);
+        for (x, y, z) in grid() {
+            for value in [
+                perlin.sample1(x),
+                perlin.sample2(x, y),
+                perlin.sample3(x, y, z),
+                simplex.sample2(x, y),
+                simplex.sample3(x, y, z),
+                tiled.sample2(x, y),
+                fbm.sample2(x, y),
+            ] {
+                assert!(value.abs() <= 1.0, "{value} is out of range");
+            }
+        }
+    }
+
+    #[test]
+    fn perlin_vanishes_on_lattice_points() {
+        let perlin = Perlin::new(11);
+        assert_eq!(perlin.sample1(4.0), 0.0);
+        assert_eq!(perlin.sample2(-2.0, 5.0), 0.0);
+        assert_eq!(perlin.sample3(1.0, 2.0, -3.0), 0.0);
+    }
+
+    fn assert_tiles<N: Noise2 + Noise3>(tiled: &N, period: f64) {
+        for (x, y, z) in grid() {
+            let (a, b) = (
+                tiled.sample2(x, y),
+                tiled.sample2(x + period, y - 2.0 * period),
+            );
+            assert!((a - b).abs() < 1e-9);
+            let (a, b) = (
+                tiled.sample3(x, y, z),
+                tiled.sample3(x, y + period, z + period),
+            );
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn tileable_noise_repeats() {
+        assert_tiles(&Fbm::new(Perlin::tileable(5, 6), 3), 6.0);
+        assert_tiles(&Fbm::new(OpenSimplex2::tileable(5, 6), 3), 6.0);
+        assert_ne!(
+            OpenSimplex2::tileable(5, 6).sample2(0.3, 0.7),
+            OpenSimplex2::tileable(5, 6).sample2(1.3, 0.7)
+        );
+    }
+}
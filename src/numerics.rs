@@ -0,0 +1,182 @@
+//! Numerical utilities for accurate floating-point accumulation and angle arithmetic.
+
+use std::f64::consts::{PI, TAU};
+use std::ops::{Add, AddAssign, Sub};
+
+/// A running sum using Kahan's compensated summation.
+///
+/// The low-order bits lost by each addition are tracked and fed back into the next one, so the
+/// error stays bounded independently of the number of terms. Works for any type with float-like
+/// addition and subtraction, notably `f32` and `f64`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KahanSum<T> {
+    sum: T,
+    compensation: T,
+}
+
+impl<T> KahanSum<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value` to the running sum.
+    pub fn push(&mut self, value: T) {
+        let corrected = value - self.compensation;
+        let total = self.sum + corrected;
+        self.compensation = (total - self.sum) - corrected;
+        self.sum = total;
+    }
+
+    /// The current value of the sum.
+    pub fn sum(&self) -> T {
+        self.sum
+    }
+}
+
+impl<T> AddAssign<T> for KahanSum<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn add_assign(&mut self, value: T) {
+        self.push(value);
+    }
+}
+
+impl<T> Extend<T> for KahanSum<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for KahanSum<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut sum = Self::new();
+        sum.extend(values);
+        sum
+    }
+}
+
+/// Sums `values` with Kahan compensated summation.
+pub fn kahan_sum<T, I>(values: I) -> T
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+    I: IntoIterator<Item = T>,
+{
+    values.into_iter().collect::<KahanSum<T>>().sum()
+}
+
+/// Sums `values` by recursively halving the slice, giving `O(log n)` error growth at the cost of
+/// requiring all terms up front.
+pub fn pairwise_sum<T>(values: &[T]) -> T
+where
+    T: Copy + Default + Add<Output = T>,
+{
+    const BLOCK: usize = 8;
+    if values.len() <= BLOCK {
+        values.iter().fold(T::default(), |sum, &value| sum + value)
+    } else {
+        let (left, right) = values.split_at(values.len() / 2);
+        pairwise_sum(left) + pairwise_sum(right)
+    }
+}
+
+/// Wraps `angle` (in radians) into `(-π, π]`.
+pub fn wrap_angle(angle: f64) -> f64 {
+    // `rem_euclid` can round up to exactly `TAU` for angles just above π.
+    let wrapped = PI - (PI - angle).rem_euclid(TAU);
+    if wrapped <= -PI {
+        PI
+    } else {
+        wrapped
+    }
+}
+
+/// Clamps `angle` to the arc swept counterclockwise from `min` to `max`.
+///
+/// Angles outside the arc snap to whichever bound is angularly closer. The result is wrapped into
+/// `(-π, π]`.
+pub fn angle_clamp(angle: f64, min: f64, max: f64) -> f64 {
+    let span = (max - min).rem_euclid(TAU);
+    let offset = (angle - min).rem_euclid(TAU);
+    if offset <= span {
+        wrap_angle(angle)
+    } else if offset - span <= TAU - offset {
+        wrap_angle(max)
+    } else {
+        wrap_angle(min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ill_conditioned() -> Vec<f32> {
+        let mut values = vec![1.0f32];
+        values.extend(std::iter::repeat_n(1e-8f32, 1_000_000));
+        values
+    }
+
+    #[test]
+    fn kahan_sum_compensates() {
+        // Naively, every small term is lost against the leading one.
+        let naive: f32 = ill_conditioned().iter().sum();
+        assert_eq!(naive, 1.0);
+        let sum = kahan_sum(ill_conditioned());
+        assert!((sum - 1.01).abs() < 1e-6, "{sum}");
+        let mut running = KahanSum::new();
+        running += 0.1f64;
+        running += 0.2;
+        running += -0.3;
+        assert!(running.sum().abs() < 1e-17);
+    }
+
+    #[test]
+    fn pairwise_sum_is_accurate() {
+        let sum = pairwise_sum(&ill_conditioned());
+        assert!((sum - 1.01).abs() < 1e-4, "{sum}");
+        assert_eq!(pairwise_sum::<f64>(&[]), 0.0);
+        assert_eq!(pairwise_sum(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]), 55);
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{a} != {b}");
+    }
+
+    #[test]
+    fn wrap_angle_range() {
+        assert_close(wrap_angle(0.0), 0.0);
+        assert_close(wrap_angle(PI), PI);
+        assert_close(wrap_angle(-PI), PI);
+        assert_close(wrap_angle(3.0 * PI / 2.0), -PI / 2.0);
+        assert_close(wrap_angle(-5.0 * TAU + 0.25), 0.25);
+        let wrapped = wrap_angle(PI.next_up());
+        assert!(wrapped > -PI && wrapped <= PI, "{wrapped}");
+    }
+
+    #[test]
+    fn angle_clamp_snaps_to_nearest_bound() {
+        let (min, max) = (-PI / 4.0, PI / 4.0);
+        assert_close(angle_clamp(0.1, min, max), 0.1);
+        assert_close(angle_clamp(TAU + 0.1, min, max), 0.1);
+        assert_close(angle_clamp(PI / 2.0, min, max), max);
+        assert_close(angle_clamp(-PI / 2.0, min, max), min);
+        // The arc from 3π/4 counterclockwise to -3π/4 crosses π.
+        let (min, max) = (3.0 * PI / 4.0, -3.0 * PI / 4.0);
+        assert_close(angle_clamp(PI, min, max), PI);
+        assert!(angle_clamp(PI.next_up(), min, max) > -PI);
+        assert_close(angle_clamp(0.5, min, max), min);
+        assert_close(angle_clamp(-0.5, min, max), max);
+    }
+}
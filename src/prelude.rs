@@ -0,0 +1,17 @@
+//! Commonly used types and traits, intended to be glob-imported:
+//!
+//! ```
+//! use gactk::prelude::*;
+//! ```
+//!
+//! # Stability
+//!
+//! Items re-exported here are part of the crate's semver-stable surface. They are only removed or
+//! renamed in a major release, regardless of how the modules that define them are reorganized, so
+//! importing through the prelude is insulated from internal layout changes. New items may be added
+//! in minor releases; if one clashes with a name in your crate, import it explicitly instead.
+
+pub use crate::modulation::{Envelope, Lfo, Modulator, SampleBuffer, Waveform};
+#[cfg(feature = "noise")]
+pub use crate::noise::{Fbm, Noise1, Noise2, Noise3, OpenSimplex2, Perlin};
+pub use crate::random::Rng;
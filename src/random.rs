@@ -0,0 +1,199 @@
+//! Seeded pseudo-random number generation.
+//!
+//! Every randomized API in the crate takes a seed or an [`Rng`], so output is reproducible: the
+//! same seed always produces the same artwork, on every platform. Seeded code sticks to integer
+//! arithmetic and basic float operations, which IEEE 754 rounds identically everywhere; the few
+//! APIs built on `std`'s transcendental functions, whose precision is platform-dependent, say so.
+
+use std::f64::consts::TAU;
+
+/// The SplitMix64 generator, used to expand a single seed into a full generator state.
+#[derive(Debug, Clone)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// A small, fast, seedable generator (xoshiro256**) with helpers for common generative tasks.
+///
+/// Not suitable for cryptography.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut seeder = SplitMix64::new(seed);
+        Self {
+            state: [
+                seeder.next_u64(),
+                seeder.next_u64(),
+                seeder.next_u64(),
+                seeder.next_u64(),
+            ],
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    /// Returns a uniformly distributed value in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a uniformly distributed value in `[min, max)`.
+    pub fn in_range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+
+    /// Returns a uniformly distributed index in `0..len`.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "Cannot pick an index from an empty range.");
+        // Lemire's multiply-and-reject method, which avoids modulo bias.
+        let range = len as u64;
+        let threshold = range.wrapping_neg() % range;
+        loop {
+            let product = self.next_u64() as u128 * range as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as usize;
+            }
+        }
+    }
+
+    /// Returns a normally distributed value with the given mean and standard deviation.
+    ///
+    /// Built on `ln` and `cos`, so the lowest bits of the result may differ between platforms
+    /// even though the generator's state advances identically.
+    pub fn gaussian(&mut self, mean: f64, std_dev: f64) -> f64 {
+        // Box–Muller transform; `1 - u` keeps the logarithm's argument in `(0, 1]`.
+        let radius = (-2.0 * (1.0 - self.next_f64()).ln()).sqrt();
+        let angle = TAU * self.next_f64();
+        mean + std_dev * radius * angle.cos()
+    }
+
+    /// Picks an index with probability proportional to its weight, or `None` if no weight is
+    /// positive. Negative weights are treated as zero.
+    pub fn weighted_choice(&mut self, weights: &[f64]) -> Option<usize> {
+        let total: f64 = weights.iter().filter(|&&weight| weight > 0.0).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut target = self.next_f64() * total;
+        let mut chosen = None;
+        for (index, &weight) in weights.iter().enumerate() {
+            if weight <= 0.0 {
+                continue;
+            }
+            chosen = Some(index);
+            target -= weight;
+            if target < 0.0 {
+                break;
+            }
+        }
+        chosen
+    }
+
+    /// Picks a uniformly random element, or `None` if `items` is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.index(items.len())])
+        }
+    }
+
+    /// Shuffles `items` in place (Fisher–Yates), with every permutation equally likely.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.index(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let (mut a, mut b) = (Rng::new(17), Rng::new(17));
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn uniform_values_are_in_range() {
+        let mut rng = Rng::new(3);
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            let value = rng.in_range(-2.0, 5.0);
+            assert!((-2.0..5.0).contains(&value));
+            seen[rng.index(7)] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn gaussian_moments() {
+        let mut rng = Rng::new(5);
+        let samples: Vec<f64> = (0..20_000).map(|_| rng.gaussian(3.0, 2.0)).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance =
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!((mean - 3.0).abs() < 0.05, "{mean}");
+        assert!((variance.sqrt() - 2.0).abs() < 0.05, "{variance}");
+    }
+
+    #[test]
+    fn weighted_choice_respects_weights() {
+        let mut rng = Rng::new(7);
+        let weights = [1.0, 0.0, 3.0, -1.0];
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            counts[rng.weighted_choice(&weights).unwrap()] += 1;
+        }
+        assert_eq!(counts[1] + counts[3], 0);
+        assert!((2700..3300).contains(&counts[2]), "{counts:?}");
+        assert_eq!(rng.weighted_choice(&[0.0, -2.0]), None);
+        assert_eq!(rng.weighted_choice(&[]), None);
+    }
+
+    #[test]
+    fn shuffle_permutes() {
+        let mut rng = Rng::new(11);
+        let mut items: Vec<usize> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+        assert_eq!(rng.choose::<u8>(&[]), None);
+        assert!([4, 5, 6].contains(rng.choose(&[4, 5, 6]).unwrap()));
+    }
+}
@@ -0,0 +1,3 @@
+//! Combinatorial tiling: arranging tiles under adjacency constraints.
+
+pub mod wfc;
@@ -0,0 +1,640 @@
+//! The simple tiled model of Wave Function Collapse.
+//!
+//! Every cell of a grid starts out able to hold any tile of a [`TileSet`]. The solver repeatedly
+//! collapses the cell with the fewest possible tiles to a single tile, chosen at random by
+//! weight, and propagates the adjacency constraints to its neighbors. When propagation empties a cell, the
+//! most recent choice is undone and excluded, backtracking as far as necessary.
+//!
+//! The output is a [`TileGrid`] of tile indices, which callers map to geometry of their choice.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fmt;
+
+use crate::random::Rng;
+
+/// A side of a grid cell. Rows grow downwards, so `Up` is the neighbor at `y - 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    /// The `(dx, dy)` step to the neighbor on this side.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Weighted tiles and the pairs of tiles allowed to sit next to each other.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TileSet {
+    weights: Vec<f64>,
+    /// `adjacency[direction][a][b]` is whether `b` may sit on the `direction` side of `a`.
+    adjacency: [Vec<Vec<bool>>; 4],
+}
+
+impl TileSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a tile with the given relative frequency, returning its index.
+    pub fn add_tile(&mut self, weight: f64) -> usize {
+        assert!(weight > 0.0, "Tile weights must be positive.");
+        self.weights.push(weight);
+        let count = self.weights.len();
+        for rules in &mut self.adjacency {
+            for row in rules.iter_mut() {
+                row.push(false);
+            }
+            rules.push(vec![false; count]);
+        }
+        count - 1
+    }
+
+    /// Allows tile `b` on the `direction` side of tile `a` (and so `a` on the opposite side of
+    /// `b`).
+    pub fn allow(&mut self, a: usize, direction: Direction, b: usize) {
+        assert!(
+            a < self.len() && b < self.len(),
+            "Tile index is out of range."
+        );
+        self.adjacency[direction.index()][a][b] = true;
+        self.adjacency[direction.opposite().index()][b][a] = true;
+    }
+
+    /// Allows tiles `a` and `b` next to each other on every side.
+    pub fn allow_all(&mut self, a: usize, b: usize) {
+        for direction in Direction::ALL {
+            self.allow(a, direction, b);
+        }
+    }
+
+    /// Whether tile `b` may sit on the `direction` side of tile `a`.
+    pub fn allows(&self, a: usize, direction: Direction, b: usize) -> bool {
+        self.adjacency[direction.index()][a][b]
+    }
+
+    pub fn weight(&self, tile: usize) -> f64 {
+        self.weights[tile]
+    }
+
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+}
+
+/// A solved grid of tile indices in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileGrid {
+    width: usize,
+    height: usize,
+    tiles: Vec<usize>,
+}
+
+impl TileGrid {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> usize {
+        assert!(x < self.width && y < self.height, "Cell is out of bounds.");
+        self.tiles[y * self.width + x]
+    }
+
+    /// The row-major tile indices.
+    pub fn tiles(&self) -> &[usize] {
+        &self.tiles
+    }
+
+    /// Iterates over every cell as `(x, y, tile)`.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(i, &tile)| (i % self.width, i / self.width, tile))
+    }
+}
+
+/// Reasons a [`Wfc`] solve can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WfcError {
+    /// The tile set has no tiles.
+    EmptyTileSet,
+    /// No arrangement satisfies the constraints within the backtracking budget.
+    Contradiction,
+}
+
+impl fmt::Display for WfcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WfcError::EmptyTileSet => write!(f, "the tile set has no tiles"),
+            WfcError::Contradiction => {
+                write!(f, "no arrangement satisfies the adjacency constraints")
+            }
+        }
+    }
+}
+
+impl Error for WfcError {}
+
+/// A Wave Function Collapse solver for a `width` by `height` grid.
+#[derive(Debug, Clone)]
+pub struct Wfc<'a> {
+    tiles: &'a TileSet,
+    width: usize,
+    height: usize,
+    periodic: bool,
+    max_backtracks: usize,
+}
+
+impl<'a> Wfc<'a> {
+    pub fn new(tiles: &'a TileSet, width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "Grid must be non-empty.");
+        Self {
+            tiles,
+            width,
+            height,
+            periodic: false,
+            max_backtracks: 1000,
+        }
+    }
+
+    /// Returns this solver with opposite grid edges treated as adjacent (or not), so the output
+    /// tiles seamlessly.
+    pub fn with_periodic(self, periodic: bool) -> Self {
+        Self { periodic, ..self }
+    }
+
+    /// Returns this solver giving up with [`WfcError::Contradiction`] after undoing
+    /// `max_backtracks` choices.
+    pub fn with_max_backtracks(self, max_backtracks: usize) -> Self {
+        Self {
+            max_backtracks,
+            ..self
+        }
+    }
+
+    /// Solves the grid, making every random choice from `seed`.
+    pub fn solve(&self, seed: u64) -> Result<TileGrid, WfcError> {
+        if self.tiles.is_empty() {
+            return Err(WfcError::EmptyTileSet);
+        }
+        let mut search = Search::new(self, seed);
+        // Each entry is the trail length before a choice, the cell chosen and the tile placed
+        // there.
+        let mut decisions: Vec<(usize, usize, usize)> = Vec::new();
+        let mut backtracks = 0;
+
+        let mut consistent = search.propagate((0..self.width * self.height).collect());
+        loop {
+            if !consistent {
+                let (trail_length, cell, tile) = decisions.pop().ok_or(WfcError::Contradiction)?;
+                backtracks += 1;
+                if backtracks > self.max_backtracks {
+                    return Err(WfcError::Contradiction);
+                }
+                search.undo(trail_length);
+                search.remove(cell, tile);
+                search.enqueue(cell);
+                consistent = search.counts[cell] > 0 && search.propagate(vec![cell]);
+                continue;
+            }
+            let cell = match search.lowest_entropy_cell() {
+                Some(cell) => cell,
+                None => break,
+            };
+            let tile = search.choose_tile(cell);
+            decisions.push((search.trail.len(), cell, tile));
+            let others: Vec<usize> = search.tiles_of(cell).filter(|&t| t != tile).collect();
+            for other in others {
+                search.remove(cell, other);
+            }
+            consistent = search.propagate(vec![cell]);
+        }
+
+        let tiles = (0..self.width * self.height)
+            .map(|cell| {
+                debug_assert_eq!(search.counts[cell], 1, "Every cell is decided.");
+                search.tiles_of(cell).next().unwrap()
+            })
+            .collect();
+        Ok(TileGrid {
+            width: self.width,
+            height: self.height,
+            tiles,
+        })
+    }
+
+    fn neighbor(&self, cell: usize, direction: Direction) -> Option<usize> {
+        let (x, y) = ((cell % self.width) as isize, (cell / self.width) as isize);
+        let (dx, dy) = direction.offset();
+        let (width, height) = (self.width as isize, self.height as isize);
+        let (mut nx, mut ny) = (x + dx, y + dy);
+        if self.periodic {
+            nx = nx.rem_euclid(width);
+            ny = ny.rem_euclid(height);
+        } else if !(0..width).contains(&nx) || !(0..height).contains(&ny) {
+            return None;
+        }
+        Some((ny * width + nx) as usize)
+    }
+}
+
+/// A candidate cell for collapse, ordered so that [`BinaryHeap`] pops the fewest possible tiles
+/// first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Candidate {
+    count: usize,
+    tie_break: u64,
+    cell: usize,
+    generation: u32,
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.count, other.tie_break, other.cell).cmp(&(self.count, self.tie_break, self.cell))
+    }
+}
+
+/// The mutable state of a solve.
+///
+/// Cells are chosen by integer keys rather than Shannon entropy, so the choice never depends on
+/// platform-specific rounding of `ln`. Each cell's possible tiles are a run of `words` bits in one flat bitset. Every removal is
+/// recorded on an undo trail, so backtracking restores exactly what later choices removed
+/// instead of snapshotting the grid. Collapse candidates live in a heap that may hold stale
+/// entries; every change to a cell's domain re-enqueues it under a new generation, and only the
+/// entry of the cell's latest generation is trusted.
+struct Search<'a> {
+    wfc: &'a Wfc<'a>,
+    rng: Rng,
+    words: usize,
+    /// `support[(direction * tiles + tile) * words..]` is the set of tiles allowed on the
+    /// `direction` side of `tile`.
+    support: Vec<u64>,
+    /// Fixed per-cell keys that break ties between equally constrained cells randomly.
+    tie_breaks: Vec<u64>,
+    domains: Vec<u64>,
+    counts: Vec<usize>,
+    generations: Vec<u32>,
+    trail: Vec<(usize, usize)>,
+    candidates: BinaryHeap<Candidate>,
+}
+
+impl<'a> Search<'a> {
+    fn new(wfc: &'a Wfc<'a>, seed: u64) -> Self {
+        let tile_count = wfc.tiles.len();
+        let cells = wfc.width * wfc.height;
+        let words = tile_count.div_ceil(64);
+        let mut support = vec![0u64; Direction::ALL.len() * tile_count * words];
+        for direction in Direction::ALL {
+            for a in 0..tile_count {
+                let offset = (direction.index() * tile_count + a) * words;
+                for b in 0..tile_count {
+                    if wfc.tiles.allows(a, direction, b) {
+                        support[offset + b / 64] |= 1 << (b % 64);
+                    }
+                }
+            }
+        }
+        let mut full = vec![u64::MAX; words];
+        if !tile_count.is_multiple_of(64) {
+            full[words - 1] = (1 << (tile_count % 64)) - 1;
+        }
+        let mut rng = Rng::new(seed);
+        let tie_breaks = (0..cells).map(|_| rng.next_u64()).collect();
+        let mut search = Self {
+            wfc,
+            rng,
+            words,
+            support,
+            tie_breaks,
+            domains: full.repeat(cells),
+            counts: vec![tile_count; cells],
+            generations: vec![0; cells],
+            trail: Vec::new(),
+            candidates: BinaryHeap::with_capacity(cells),
+        };
+        for cell in 0..cells {
+            search.enqueue(cell);
+        }
+        search
+    }
+
+    fn tiles_of(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        let domain = &self.domains[cell * self.words..(cell + 1) * self.words];
+        domain.iter().enumerate().flat_map(|(index, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(index * 64 + bit)
+            })
+        })
+    }
+
+    fn remove(&mut self, cell: usize, tile: usize) {
+        self.domains[cell * self.words + tile / 64] &= !(1 << (tile % 64));
+        self.counts[cell] -= 1;
+        self.trail.push((cell, tile));
+    }
+
+    /// Restores every removal recorded after the trail had `length` entries.
+    fn undo(&mut self, length: usize) {
+        let mut restored = Vec::new();
+        for (cell, tile) in self.trail.drain(length..).rev() {
+            self.domains[cell * self.words + tile / 64] |= 1 << (tile % 64);
+            self.counts[cell] += 1;
+            restored.push(cell);
+        }
+        restored.sort_unstable();
+        restored.dedup();
+        for cell in restored {
+            self.enqueue(cell);
+        }
+    }
+
+    /// Invalidates the queued entries of `cell` and makes it a collapse candidate again if it is
+    /// undecided. Must be called whenever the cell's domain changes.
+    fn enqueue(&mut self, cell: usize) {
+        self.generations[cell] = self.generations[cell].wrapping_add(1);
+        if self.counts[cell] >= 2 {
+            self.candidates.push(Candidate {
+                count: self.counts[cell],
+                tie_break: self.tie_breaks[cell],
+                cell,
+                generation: self.generations[cell],
+            });
+        }
+    }
+
+    /// Removes tiles unsupported by their neighbors, starting from `pending`. Returns `false` if
+    /// a cell runs out of possible tiles.
+    fn propagate(&mut self, mut pending: Vec<usize>) -> bool {
+        let tile_count = self.wfc.tiles.len();
+        let mut allowed = vec![0u64; self.words];
+        while let Some(cell) = pending.pop() {
+            for direction in Direction::ALL {
+                let neighbor = match self.wfc.neighbor(cell, direction) {
+                    Some(neighbor) => neighbor,
+                    None => continue,
+                };
+                allowed.fill(0);
+                for tile in self.tiles_of(cell) {
+                    let offset = (direction.index() * tile_count + tile) * self.words;
+                    for (word, support) in allowed.iter_mut().zip(&self.support[offset..]) {
+                        *word |= support;
+                    }
+                }
+                let mut changed = false;
+                for (index, &word) in allowed.iter().enumerate() {
+                    let mut unsupported = self.domains[neighbor * self.words + index] & !word;
+                    while unsupported != 0 {
+                        let bit = unsupported.trailing_zeros() as usize;
+                        unsupported &= unsupported - 1;
+                        self.remove(neighbor, index * 64 + bit);
+                        changed = true;
+                    }
+                }
+                if changed {
+                    if self.counts[neighbor] == 0 {
+                        return false;
+                    }
+                    self.enqueue(neighbor);
+                    pending.push(neighbor);
+                }
+            }
+        }
+        true
+    }
+
+    /// The undecided cell with the fewest possible tiles, with ties broken randomly, or `None` if
+    /// every cell is decided.
+    fn lowest_entropy_cell(&mut self) -> Option<usize> {
+        while let Some(Candidate {
+            cell, generation, ..
+        }) = self.candidates.pop()
+        {
+            if self.counts[cell] >= 2 && self.generations[cell] == generation {
+                return Some(cell);
+            }
+        }
+        None
+    }
+
+    fn choose_tile(&mut self, cell: usize) -> usize {
+        let mut weights = vec![0.0; self.wfc.tiles.len()];
+        for tile in self.tiles_of(cell) {
+            weights[tile] = self.wfc.tiles.weight(tile);
+        }
+        self.rng
+            .weighted_choice(&weights)
+            .expect("Undecided cells have at least one possible tile.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid(tiles: &TileSet, grid: &TileGrid, periodic: bool) {
+        let (width, height) = (grid.width(), grid.height());
+        for (x, y, tile) in grid.cells() {
+            if periodic || x + 1 < width {
+                let right = grid.get((x + 1) % width, y);
+                assert!(tiles.allows(tile, Direction::Right, right), "({x}, {y})");
+            }
+            if periodic || y + 1 < height {
+                let below = grid.get(x, (y + 1) % height);
+                assert!(tiles.allows(tile, Direction::Down, below), "({x}, {y})");
+            }
+        }
+    }
+
+    fn checkerboard() -> TileSet {
+        let mut tiles = TileSet::new();
+        let (black, white) = (tiles.add_tile(1.0), tiles.add_tile(1.0));
+        tiles.allow_all(black, white);
+        tiles
+    }
+
+    #[test]
+    fn solves_checkerboard() {
+        let tiles = checkerboard();
+        let grid = Wfc::new(&tiles, 7, 5).solve(3).unwrap();
+        assert_valid(&tiles, &grid, false);
+        assert_ne!(grid.get(0, 0), grid.get(1, 0));
+    }
+
+    #[test]
+    fn periodic_grids_wrap_constraints() {
+        let tiles = checkerboard();
+        let grid = Wfc::new(&tiles, 6, 4).with_periodic(true).solve(0).unwrap();
+        assert_valid(&tiles, &grid, true);
+        assert_eq!(
+            Wfc::new(&tiles, 5, 4).with_periodic(true).solve(0),
+            Err(WfcError::Contradiction)
+        );
+    }
+
+    #[test]
+    fn backtracks_out_of_dead_ends() {
+        // Horizontal and vertical neighbors among tiles 0-2 are related by permutations that
+        // don't commute, so every 2x2 block of them is contradictory even though each pair of
+        // neighbors is allowed. Only the rare tile 3, which neighbors itself, can fill the grid.
+        let mut tiles = TileSet::new();
+        for weight in [1.0, 1.0, 1.0, 0.01] {
+            tiles.add_tile(weight);
+        }
+        for (a, b) in [(0, 1), (1, 0), (2, 2)] {
+            tiles.allow(a, Direction::Right, b);
+        }
+        for (a, b) in [(0, 0), (1, 2), (2, 1)] {
+            tiles.allow(a, Direction::Down, b);
+        }
+        tiles.allow_all(3, 3);
+        for seed in 0..10 {
+            let grid = Wfc::new(&tiles, 3, 3).solve(seed).unwrap();
+            assert!(grid.tiles().iter().all(|&tile| tile == 3));
+        }
+        assert!((0..10).any(|seed| Wfc::new(&tiles, 3, 3)
+            .with_max_backtracks(0)
+            .solve(seed)
+            .is_err()));
+    }
+
+    #[test]
+    fn backtracking_collapses_every_restored_cell() {
+        // A cell restored by backtracking and then narrowed by excluding the failed choice must
+        // still be collapsed, rather than falling back to its lowest tile.
+        let mut tiles = TileSet::new();
+        for weight in [0.662, 0.503, 1.665, 0.424] {
+            tiles.add_tile(weight);
+        }
+        for (a, b) in [
+            (0, 3),
+            (1, 1),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+            (3, 0),
+            (3, 2),
+        ] {
+            tiles.allow(a, Direction::Right, b);
+        }
+        for (a, b) in [
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 2),
+            (2, 1),
+            (2, 2),
+            (3, 2),
+            (3, 3),
+        ] {
+            tiles.allow(a, Direction::Down, b);
+        }
+        for seed in 0..50 {
+            if let Ok(grid) = Wfc::new(&tiles, 7, 3).solve(seed) {
+                assert_valid(&tiles, &grid, false);
+            }
+        }
+        assert_valid(&tiles, &Wfc::new(&tiles, 7, 3).solve(4).unwrap(), false);
+    }
+
+    #[test]
+    fn reports_unsatisfiable_and_empty_sets() {
+        let mut tiles = TileSet::new();
+        tiles.add_tile(1.0);
+        assert_eq!(
+            Wfc::new(&tiles, 2, 1).solve(0),
+            Err(WfcError::Contradiction)
+        );
+        assert_eq!(
+            Wfc::new(&TileSet::new(), 2, 2).solve(0),
+            Err(WfcError::EmptyTileSet)
+        );
+    }
+
+    #[test]
+    fn solves_with_many_tiles() {
+        // More tiles than fit in one word of the domain bitset.
+        let mut tiles = TileSet::new();
+        for _ in 0..70 {
+            tiles.add_tile(1.0);
+        }
+        for a in 0..70 {
+            for b in 0..70 {
+                if a != b {
+                    tiles.allow_all(a, b);
+                }
+            }
+        }
+        assert_valid(&tiles, &Wfc::new(&tiles, 12, 12).solve(9).unwrap(), false);
+    }
+
+    #[test]
+    fn solves_large_grids() {
+        // Each tile may only neighbor itself and the adjacent tiles in a chain.
+        let mut tiles = TileSet::new();
+        for _ in 0..8 {
+            tiles.add_tile(1.0);
+        }
+        for tile in 0..8 {
+            tiles.allow_all(tile, tile);
+            if tile + 1 < 8 {
+                tiles.allow_all(tile, tile + 1);
+            }
+        }
+        let grid = Wfc::new(&tiles, 128, 128).solve(1).unwrap();
+        assert_valid(&tiles, &grid, false);
+    }
+}